@@ -1,9 +1,17 @@
+use std::fs;
+use std::path::PathBuf;
 use std::time::Duration;
+use bevy::asset::LoadState;
+use bevy::audio::Volume;
 use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
 
 const BIRD_SCALE: f32 = 1.0; // Adjust this value to change the bird's size
 const GROUND_SCALE: f32 = 2.0; // Adjust this value to change the ground's size
 const PIPE_SCALE: Vec3 = Vec3::new(3., 5., 1.); // Adjust this value to change the pipe's size
+const GAP_HEIGHT: f32 = 220.0; // Vertical opening the bird flies through between paired pipes
+const BIRD_SPEED: f32 = 120.0; // Constant horizontal speed (px/s) driven through rapier
+const FLAP_SPEED: f32 = 320.0; // Upward linear velocity (px/s) applied on each flap
 
 #[derive(Component, Debug)]
 struct Pipe;
@@ -20,11 +28,99 @@ pub struct AnimationIndices {
     last: usize,
 }
 
+/// Top-level lifecycle of the game.
+///
+/// Each screen owns its own setup/teardown through `OnEnter`/`OnExit` and the
+/// per-frame systems are gated with `run_if(in_state(..))` so we no longer have
+/// to thread a `is_game_over` flag through every system.
+#[derive(States, Default, Debug, Clone, PartialEq, Eq, Hash)]
+pub enum AppState {
+    #[default]
+    Loading,
+    Menu,
+    Playing,
+    Paused,
+    GameOver,
+}
+
+/// All texture handles used by the game, cloned out where sprites are spawned.
+#[derive(Default)]
+pub struct Images {
+    pub player: Handle<Image>,
+    pub pipe: Handle<Image>,
+    pub ground: Handle<Image>,
+    pub background: Handle<Image>,
+}
+
+/// Pre-built texture atlas layouts for the animated sprites.
+#[derive(Default)]
+pub struct Layouts {
+    pub player: Handle<TextureAtlasLayout>,
+    pub pipe: Handle<TextureAtlasLayout>,
+}
+
+#[derive(Default)]
+pub struct Fonts {
+    pub fira: Handle<Font>,
+}
+
+/// Single home for every asset handle, populated once in `load_assets` so the
+/// spawn systems never touch the `AssetServer` directly or re-load on restart.
+#[derive(Resource, Default)]
+pub struct AssetLoader {
+    pub images: Images,
+    pub layouts: Layouts,
+    pub fonts: Fonts,
+}
+
+/// Live and persisted score. `best` is carried across restarts and written to
+/// disk on game over; `current` is reset whenever a new run starts.
+#[derive(Resource, Debug, Default)]
+pub struct Score {
+    pub current: u32,
+    pub best: u32,
+}
+
+/// Marks the column between a pair of pipes. Once the player's `x` passes
+/// `self.x` the run's score is incremented and `passed` is flipped so the same
+/// column never scores twice.
+#[derive(Component, Debug)]
+pub struct ScoreZone {
+    pub x: f32,
+    pub passed: bool,
+}
+
+#[derive(Component, Debug)]
+pub struct ScoreText;
+
+/// Centralized sound effect and music handles, loaded once at startup.
 #[derive(Resource)]
-pub struct GameState {
-    pub is_game_over: bool,
+pub struct Sounds {
+    pub flap: Handle<AudioSource>,
+    pub hit: Handle<AudioSource>,
+    pub score: Handle<AudioSource>,
+    pub music: Handle<AudioSource>,
+}
+
+/// Global playback controls toggled at runtime with the `M` key.
+#[derive(Resource)]
+pub struct AudioSettings {
+    pub muted: bool,
+    pub volume: f32,
+}
+
+impl Default for AudioSettings {
+    fn default() -> Self {
+        Self {
+            muted: false,
+            volume: 0.5,
+        }
+    }
 }
 
+#[derive(Component, Debug)]
+pub struct BackgroundMusic;
+
 #[derive(Event)]
 pub struct GameOverEvent;
 
@@ -41,31 +137,41 @@ pub struct Ground;
 pub struct GameOverText;
 
 #[derive(Component, Debug)]
-pub struct Velocity {
-    pub value: Vec2,
-}
+pub struct MenuText;
 
 #[derive(Component, Debug)]
-pub struct Gravity;
+pub struct Player;
 
+/// Visual child of the bird. rapier only writes the parent body's `Transform`,
+/// so tilting this child's rotation survives the physics writeback.
 #[derive(Component, Debug)]
-pub struct Player;
+pub struct PlayerSprite;
 
-#[derive(Bundle, Debug)]
+/// The bird as a rapier dynamic body: gravity and motion are driven by the
+/// physics backend, rotation is locked so the body stays upright, and
+/// collision events are enabled so game-over can read contacts. The sprite
+/// lives on a child entity (`PlayerSprite`) which we tilt ourselves.
+#[derive(Bundle)]
 pub struct PlayerBundle {
-    pub velocity: Velocity,
-    pub gravity: Gravity,
     pub player: Player,
+    pub rigid_body: RigidBody,
+    pub collider: Collider,
+    pub velocity: Velocity,
+    pub gravity_scale: GravityScale,
+    pub locked_axes: LockedAxes,
+    pub active_events: ActiveEvents,
 }
 
 impl Default for PlayerBundle {
     fn default() -> Self {
         Self {
-            velocity: Velocity {
-                value: Vec2::new(2., 0.),
-            },
-            gravity: Gravity {},
             player: Player {},
+            rigid_body: RigidBody::Dynamic,
+            collider: Collider::cuboid(90.0 * BIRD_SCALE, 50.0 * BIRD_SCALE),
+            velocity: Velocity::linear(Vec2::new(BIRD_SPEED, 0.0)),
+            gravity_scale: GravityScale(3.0),
+            locked_axes: LockedAxes::ROTATION_LOCKED,
+            active_events: ActiveEvents::COLLISION_EVENTS,
         }
     }
 }
@@ -73,36 +179,206 @@ impl Default for PlayerBundle {
 pub struct GamePlugin;
 impl Plugin for GamePlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Startup, (setup_level, spawn_player).chain())
+        #[cfg(feature = "debug")]
+        app.add_plugins(DebugPlugin);
+
+        app.add_plugins(GameAudioPlugin)
+            .init_state::<AppState>()
+            .insert_resource(Score {
+                current: 0,
+                best: load_best_score(),
+            })
+            .add_systems(Startup, (spawn_camera, load_assets, setup_score_ui).chain())
+            .add_systems(Update, check_assets_loaded.run_if(in_state(AppState::Loading)))
+            .add_systems(OnEnter(AppState::Menu), setup_menu)
+            .add_systems(OnExit(AppState::Menu), despawn_menu)
+            .add_systems(
+                OnEnter(AppState::Playing),
+                (setup_level, spawn_player)
+                    .chain()
+                    .run_if(level_not_spawned),
+            )
+            .add_systems(OnEnter(AppState::GameOver), spawn_game_over_text)
+            .add_systems(OnExit(AppState::GameOver), despawn_game_over_text)
+            .add_systems(OnEnter(AppState::Paused), pause_physics)
+            .add_systems(OnExit(AppState::Paused), resume_physics)
+            .add_systems(Update, start_game.run_if(in_state(AppState::Menu)))
+            .add_systems(Update, toggle_pause)
+            .add_systems(
+                Update,
+                (update_velocity_on_space, check_collision)
+                    .run_if(in_state(AppState::Playing)),
+            )
             .add_systems(
                 Update,
                 (
-                    update_velocity_on_space,
+                    persist_best_score,
                     handle_game_over,
                     check_for_restart,
                     animate_sprite,
+                    update_score_text,
                 ),
             )
             .add_systems(
                 FixedUpdate,
-                (
-                    update_player_position,
-                    apply_gravity,
-                    check_collision,
-                    camera_follow_player,
-                    update_bg_position,
-                )
-                    .chain(),
+                (rotate_player_with_velocity, score_system)
+                    .chain()
+                    .run_if(in_state(AppState::Playing)),
             )
+            .add_systems(FixedUpdate, (camera_follow_player, update_bg_position).chain())
             .add_systems(Update, handle_restart_event)
-            .insert_resource(GameState {
-                is_game_over: false,
-            })
             .add_event::<GameOverEvent>()
             .add_event::<RestartEvent>();
     }
 }
 
+/// Owns the game's sound effects and music: loads the handles, starts looping
+/// background music when a run begins, and exposes a runtime mute toggle.
+pub struct GameAudioPlugin;
+impl Plugin for GameAudioPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<AudioSettings>()
+            .add_systems(Startup, load_sounds)
+            .add_systems(
+                OnEnter(AppState::Playing),
+                start_music.run_if(music_not_playing),
+            )
+            .add_systems(Update, toggle_mute);
+    }
+}
+
+fn load_sounds(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(Sounds {
+        flap: asset_server.load("sounds/flap.wav"),
+        hit: asset_server.load("sounds/hit.wav"),
+        score: asset_server.load("sounds/score.wav"),
+        music: asset_server.load("sounds/music.wav"),
+    });
+}
+
+/// Playback settings for a one-shot effect, respecting the global mute/volume.
+fn one_shot(settings: &AudioSettings) -> PlaybackSettings {
+    let volume = if settings.muted { 0.0 } else { settings.volume };
+    PlaybackSettings::DESPAWN.with_volume(Volume::new(volume))
+}
+
+/// Run condition: no background music entity exists yet, so we don't stack a
+/// second looping track when unpausing or restarting.
+fn music_not_playing(query: Query<(), With<BackgroundMusic>>) -> bool {
+    query.is_empty()
+}
+
+fn start_music(mut commands: Commands, sounds: Res<Sounds>, settings: Res<AudioSettings>) {
+    let volume = if settings.muted { 0.0 } else { settings.volume };
+    commands.spawn((
+        AudioBundle {
+            source: sounds.music.clone(),
+            settings: PlaybackSettings::LOOP.with_volume(Volume::new(volume)),
+        },
+        BackgroundMusic,
+    ));
+}
+
+fn toggle_mute(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut settings: ResMut<AudioSettings>,
+    sinks: Query<&AudioSink>,
+) {
+    if keyboard_input.just_pressed(KeyCode::KeyM) {
+        settings.muted = !settings.muted;
+        let volume = if settings.muted { 0.0 } else { settings.volume };
+        for sink in &sinks {
+            sink.set_volume(volume);
+        }
+    }
+}
+
+/// Runtime diagnostics overlay, compiled in only with the `debug` feature.
+///
+/// Registers Bevy's frame-time diagnostics and draws FPS, live entity count
+/// and the player's `Velocity`/`Transform` in a corner, toggled with `F3`.
+#[cfg(feature = "debug")]
+pub struct DebugPlugin;
+
+#[cfg(feature = "debug")]
+impl Plugin for DebugPlugin {
+    fn build(&self, app: &mut App) {
+        use bevy::diagnostic::FrameTimeDiagnosticsPlugin;
+        app.add_plugins(FrameTimeDiagnosticsPlugin)
+            // Ordered after `load_assets` so the inserted `AssetLoader` resource
+            // is visible past the auto sync-point when we read the font handle.
+            .add_systems(Startup, spawn_debug_text.after(load_assets))
+            .add_systems(Update, (toggle_debug_overlay, update_debug_text));
+    }
+}
+
+#[cfg(feature = "debug")]
+#[derive(Component, Debug)]
+pub struct DebugText;
+
+#[cfg(feature = "debug")]
+fn spawn_debug_text(mut commands: Commands, assets: Res<AssetLoader>) {
+    commands.spawn((
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font: assets.fonts.fira.clone(),
+                font_size: 18.0,
+                color: Color::srgb(0.0, 1.0, 0.0),
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(10.0),
+            left: Val::Px(10.0),
+            ..default()
+        }),
+        DebugText,
+    ));
+}
+
+#[cfg(feature = "debug")]
+fn toggle_debug_overlay(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut query: Query<&mut Visibility, With<DebugText>>,
+) {
+    if keyboard_input.just_pressed(KeyCode::F3) {
+        for mut visibility in &mut query {
+            *visibility = match *visibility {
+                Visibility::Hidden => Visibility::Visible,
+                _ => Visibility::Hidden,
+            };
+        }
+    }
+}
+
+#[cfg(feature = "debug")]
+fn update_debug_text(
+    diagnostics: Res<bevy::diagnostic::DiagnosticsStore>,
+    entities: Query<Entity>,
+    player_query: Query<(&Velocity, &Transform), With<Player>>,
+    mut query: Query<&mut Text, With<DebugText>>,
+) {
+    use bevy::diagnostic::FrameTimeDiagnosticsPlugin;
+
+    let fps = diagnostics
+        .get(&FrameTimeDiagnosticsPlugin::FPS)
+        .and_then(|fps| fps.smoothed())
+        .unwrap_or(0.0);
+    let entity_count = entities.iter().count();
+    let player = player_query.get_single().ok();
+
+    for mut text in &mut query {
+        text.sections[0].value = match player {
+            Some((velocity, transform)) => format!(
+                "FPS: {:.1}\nEntities: {}\nVel: {:.2?}\nPos: {:.1?}",
+                fps, entity_count, velocity.linvel, transform.translation
+            ),
+            None => format!("FPS: {:.1}\nEntities: {}\nVel: -\nPos: -", fps, entity_count),
+        };
+    }
+}
+
 fn main() {
     App::new()
         .add_plugins((
@@ -114,39 +390,91 @@ fn main() {
                 }),
                 ..default()
             }),
+            RapierPhysicsPlugin::<NoUserData>::pixels_per_meter(100.0),
             GamePlugin,
         ))
         .insert_resource(Time::<Fixed>::from_duration(Duration::from_millis(16)))
         .run();
 }
 
-fn spawn_player(
+/// Run condition: the level hasn't been spawned yet, so we only build it when
+/// entering `Playing` from the menu or after a restart — not when unpausing.
+fn level_not_spawned(query: Query<(), With<Player>>) -> bool {
+    query.is_empty()
+}
+
+fn load_assets(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
     mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
 ) {
-    let texture_handle = asset_server.load("textures/mooslisprites.png");
-    let layout = TextureAtlasLayout::from_grid(UVec2::new(180, 100), 2, 1, None, None);
-    let texture_atlas_layout = texture_atlas_layouts.add(layout);
-    let animation_indices = AnimationIndices { first: 0, last: 1 };
-    commands.spawn((
-        SpriteBundle {
-            texture: texture_handle,
-            transform: Transform {
-                translation: Vec3::new(0.0, 0.0, 2.0), // Position the player at the center
-                scale: Vec3::splat(BIRD_SCALE),
-                ..Default::default()
-            },
-            ..Default::default()
+    let player_layout =
+        texture_atlas_layouts.add(TextureAtlasLayout::from_grid(UVec2::new(180, 100), 2, 1, None, None));
+    let pipe_layout =
+        texture_atlas_layouts.add(TextureAtlasLayout::from_grid(UVec2::new(32, 48), 4, 2, None, None));
+    commands.insert_resource(AssetLoader {
+        images: Images {
+            player: asset_server.load("textures/mooslisprites.png"),
+            pipe: asset_server.load("textures/PipeStyle5.png"),
+            ground: asset_server.load("textures/ground.png"),
+            background: asset_server.load("textures/Background5.png"),
         },
-        TextureAtlas {
-            layout: texture_atlas_layout,
-            index: animation_indices.first,
+        layouts: Layouts {
+            player: player_layout,
+            pipe: pipe_layout,
         },
-        PlayerBundle::default(),
-        animation_indices,
-        AnimationTimer(Timer::from_seconds(0.3, TimerMode::Repeating)),
-    ));
+        fonts: Fonts {
+            fira: asset_server.load("fonts/FiraSans-Bold.ttf"),
+        },
+    });
+}
+
+/// Hold in `Loading` until every texture and font handle reports `Loaded`, so
+/// the first visible frame never shows a missing texture.
+fn check_assets_loaded(
+    asset_server: Res<AssetServer>,
+    assets: Res<AssetLoader>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    let pending = [
+        asset_server.get_load_state(assets.images.player.id()),
+        asset_server.get_load_state(assets.images.pipe.id()),
+        asset_server.get_load_state(assets.images.ground.id()),
+        asset_server.get_load_state(assets.images.background.id()),
+        asset_server.get_load_state(assets.fonts.fira.id()),
+    ];
+    if pending
+        .iter()
+        .all(|state| matches!(state, Some(LoadState::Loaded)))
+    {
+        next_state.set(AppState::Menu);
+    }
+}
+
+fn spawn_player(mut commands: Commands, assets: Res<AssetLoader>) {
+    let animation_indices = AnimationIndices { first: 0, last: 1 };
+    // The physics body carries no sprite; its Transform is owned by rapier.
+    commands
+        .spawn((
+            PlayerBundle::default(),
+            SpatialBundle::from_transform(Transform::from_xyz(0.0, 0.0, 2.0)),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                SpriteBundle {
+                    texture: assets.images.player.clone(),
+                    transform: Transform::from_scale(Vec3::splat(BIRD_SCALE)),
+                    ..Default::default()
+                },
+                TextureAtlas {
+                    layout: assets.layouts.player.clone(),
+                    index: animation_indices.first,
+                },
+                PlayerSprite,
+                animation_indices,
+                AnimationTimer(Timer::from_seconds(0.3, TimerMode::Repeating)),
+            ));
+        });
 }
 
 fn animate_sprite(
@@ -165,57 +493,126 @@ fn animate_sprite(
     }
 }
 
-fn update_player_position(mut query: Query<(&Velocity, &mut Transform), With<Player>>) {
-    for (velocity, mut transform) in query.iter_mut() {
-        transform.translation.x += velocity.value.x;
-        transform.translation.y += velocity.value.y;
-    }
-}
-
 fn update_bg_position(
     camera_query: Query<(&Transform, &GameCamera), Without<Background>>,
     mut bg_query: Query<&mut Transform, With<Background>>,
 ) {
-    let (camera, _) = camera_query.single();
-    let mut bg = bg_query.single_mut();
+    let Ok((camera, _)) = camera_query.get_single() else {
+        return;
+    };
+    let Ok(mut bg) = bg_query.get_single_mut() else {
+        return;
+    };
 
     bg.translation.x = camera.translation.x;
 }
 
-fn apply_gravity(mut query: Query<&mut Velocity, With<Gravity>>) {
-    for mut velocity in query.iter_mut() {
-        velocity.value.y += -0.1;
+/// Tilt the bird toward its direction of travel: a gentle nose-up while rising
+/// and a progressively steeper dive while falling, eased toward the target
+/// angle each tick instead of jumping to it. A flap sets the tilt directly in
+/// `update_velocity_on_space`.
+fn rotate_player_with_velocity(
+    player_query: Query<&Velocity, With<Player>>,
+    mut sprite_query: Query<&mut Transform, With<PlayerSprite>>,
+) {
+    const MAX_UP: f32 = std::f32::consts::FRAC_PI_6; // +30°
+    const MAX_DOWN: f32 = -std::f32::consts::FRAC_PI_2; // -90°
+    const LERP_FACTOR: f32 = 0.1;
+
+    let Ok(velocity) = player_query.get_single() else {
+        return;
+    };
+    let target = (velocity.linvel.y * 0.005).clamp(MAX_DOWN, MAX_UP);
+    let rotation = Quat::from_rotation_z(target);
+    for mut transform in &mut sprite_query {
+        transform.rotation = transform.rotation.slerp(rotation, LERP_FACTOR);
     }
 }
 
-fn spawn_ground(commands: &mut Commands, asset_server: &Res<AssetServer>) {
-    let ground_image = asset_server.load("textures/ground.png");
+fn spawn_ground(commands: &mut Commands, assets: &AssetLoader) {
     for i in 0..100 {
         commands.spawn((
             SpriteBundle {
-                texture: ground_image.clone(),
+                texture: assets.images.ground.clone(),
                 transform: Transform::from_xyz(64. * ((i as f32) - 5.), -268., 0.0)
                     .with_scale(Vec3::splat(GROUND_SCALE)),
                 ..default()
             },
+            RigidBody::Fixed,
+            // Local half-extents; rapier scales them by the transform's GROUND_SCALE.
+            Collider::cuboid(32.0, 16.0),
             Ground,
         ));
     }
 }
 
-fn spawn_camera(commands: &mut Commands) {
+fn spawn_camera(mut commands: Commands) {
     commands.spawn((Camera2dBundle::default(), GameCamera));
 }
 
-fn setup_level(
-    mut commands: Commands,
-    asset_server: Res<AssetServer>,
-    mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
+fn setup_level(mut commands: Commands, assets: Res<AssetLoader>) {
+    setup_background(&mut commands, &assets);
+    spawn_ground(&mut commands, &assets);
+    spawn_pipe(&mut commands, &assets);
+}
+
+fn setup_menu(mut commands: Commands, assets: Res<AssetLoader>) {
+    commands.spawn((
+        TextBundle::from_section(
+            "Flappy Circle\nPress Space to start",
+            TextStyle {
+                font: assets.fonts.fira.clone(),
+                font_size: 40.0,
+                color: Color::WHITE,
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            top: Val::Px(100.0),
+            left: Val::Px(250.0),
+            ..default()
+        }),
+        MenuText,
+    ));
+}
+
+fn despawn_menu(mut commands: Commands, menu_query: Query<Entity, With<MenuText>>) {
+    for entity in menu_query.iter() {
+        commands.entity(entity).despawn();
+    }
+}
+
+fn start_game(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    if keyboard_input.just_pressed(KeyCode::Space) {
+        next_state.set(AppState::Playing);
+    }
+}
+
+/// Freeze the rapier simulation while paused so the bird stops falling and
+/// advancing, then resume it on unpause — entities are left untouched.
+fn pause_physics(mut config: ResMut<RapierConfiguration>) {
+    config.physics_pipeline_active = false;
+}
+
+fn resume_physics(mut config: ResMut<RapierConfiguration>) {
+    config.physics_pipeline_active = true;
+}
+
+fn toggle_pause(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    state: Res<State<AppState>>,
+    mut next_state: ResMut<NextState<AppState>>,
 ) {
-    spawn_camera(&mut commands);
-    setup_background(&mut commands, &asset_server);
-    spawn_ground(&mut commands, &asset_server);
-    spawn_pipe(&mut commands, &asset_server, &mut texture_atlas_layouts);
+    if keyboard_input.just_pressed(KeyCode::KeyP) {
+        match state.get() {
+            AppState::Playing => next_state.set(AppState::Paused),
+            AppState::Paused => next_state.set(AppState::Playing),
+            _ => {}
+        }
+    }
 }
 
 fn camera_follow_player(
@@ -234,130 +631,141 @@ fn camera_follow_player(
 }
 
 fn update_velocity_on_space(
+    mut commands: Commands,
     keyboard_input: Res<ButtonInput<KeyCode>>,
-    mut query: Query<(&mut Velocity, &mut TextureAtlas), With<Player>>,
-    game_state: Res<GameState>,
+    mut player_query: Query<&mut Velocity, With<Player>>,
+    mut sprite_query: Query<(&mut Transform, &mut TextureAtlas), With<PlayerSprite>>,
+    sounds: Res<Sounds>,
+    audio_settings: Res<AudioSettings>,
 ) {
-    if !game_state.is_game_over && keyboard_input.just_pressed(KeyCode::Space) {
-        for (mut velocity, mut sprite) in query.iter_mut() {
-            velocity.value.y = 5.0;
+    if keyboard_input.just_pressed(KeyCode::Space) {
+        for mut velocity in player_query.iter_mut() {
+            velocity.linvel.y = FLAP_SPEED;
+        }
+        for (mut transform, mut sprite) in sprite_query.iter_mut() {
             sprite.index = 0; // Reset animation to first frame when jumping
+            // Snap the nose back up on each flap.
+            transform.rotation = Quat::from_rotation_z(std::f32::consts::FRAC_PI_6);
         }
+        commands.spawn(AudioBundle {
+            source: sounds.flap.clone(),
+            settings: one_shot(&audio_settings),
+        });
     }
 }
 
 fn check_collision(
     mut commands: Commands,
-    player_query: Query<(Entity, &Transform), With<Player>>,
-    ground_query: Query<&Transform, With<Ground>>,
-    pipe_query: Query<&Transform, With<Pipe>>,
+    mut collision_events: EventReader<CollisionEvent>,
+    player_query: Query<Entity, With<Player>>,
     mut game_over_events: EventWriter<GameOverEvent>,
-    game_state: Res<GameState>,
+    sounds: Res<Sounds>,
+    audio_settings: Res<AudioSettings>,
 ) {
-    if game_state.is_game_over {
+    let Ok(player_entity) = player_query.get_single() else {
         return;
-    }
-
-    let (player_entity, player_transform) = player_query.single();
-    let ground_transform = ground_query.iter().next().unwrap();
-
-    let player_y = player_transform.translation.y - (50.0 * BIRD_SCALE);
-    let ground_y = ground_transform.translation.y + (16.0 * GROUND_SCALE);
-
-    if player_y <= ground_y {
-        game_over_events.send(GameOverEvent);
-        commands.entity(player_entity).despawn();
-    } else {
-        for pipe_transform in pipe_query.iter() {
-            let pipe_x = pipe_transform.translation.x;
-            let pipe_y = pipe_transform.translation.y;
-            let player_x = player_transform.translation.x;
-            let player_y = player_transform.translation.y;
-            let pipe_half_w = (32. * PIPE_SCALE.x) / 2.0;
-            let pipe_half_h = (48. * PIPE_SCALE.y) / 2.0;
-            let player_half_w = - (90.0 * BIRD_SCALE) / 2.0;
-            let player_half_h = (50.0 * BIRD_SCALE) / 2.0;
-            if player_x + player_half_w >= pipe_x - pipe_half_w && player_x - player_half_w <= pipe_x + pipe_half_w {
-                if player_y + player_half_h >= pipe_y - pipe_half_h && player_y - player_half_h <= pipe_y + pipe_half_h {
-                    game_over_events.send(GameOverEvent);
-                    commands.entity(player_entity).despawn();
-                    break;
-                }
+    };
+
+    for event in collision_events.read() {
+        // Any started contact involving the bird (a pipe or the ground) ends the run.
+        if let CollisionEvent::Started(first, second, _) = event {
+            if *first == player_entity || *second == player_entity {
+                game_over_events.send(GameOverEvent);
+                commands.spawn(AudioBundle {
+                    source: sounds.hit.clone(),
+                    settings: one_shot(&audio_settings),
+                });
+                commands.entity(player_entity).despawn_recursive();
+                break;
             }
         }
     }
 }
 
 fn handle_game_over(
-    mut commands: Commands,
-    mut game_state: ResMut<GameState>,
     mut game_over_events: EventReader<GameOverEvent>,
-    asset_server: Res<AssetServer>,
+    mut next_state: ResMut<NextState<AppState>>,
 ) {
-    for _ in game_over_events.read() {
-        if !game_state.is_game_over {
-            game_state.is_game_over = true;
-
-            commands.spawn((
-                TextBundle::from_section(
-                    "Game over!\nPress R to restart",
-                    TextStyle {
-                        font: asset_server.load("fonts/FiraSans-Bold.ttf"),
-                        font_size: 40.0,
-                        color: Color::WHITE,
-                    },
-                )
-                .with_style(Style {
-                    position_type: PositionType::Absolute,
-                    top: Val::Px(100.0),
-                    left: Val::Px(400.0),
-                    ..default()
-                }),
-                GameOverText,
-            ));
-        }
+    if !game_over_events.is_empty() {
+        game_over_events.clear();
+        next_state.set(AppState::GameOver);
+    }
+}
+
+fn spawn_game_over_text(mut commands: Commands, assets: Res<AssetLoader>) {
+    commands.spawn((
+        TextBundle::from_section(
+            "Game over!\nPress R to restart",
+            TextStyle {
+                font: assets.fonts.fira.clone(),
+                font_size: 40.0,
+                color: Color::WHITE,
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            top: Val::Px(100.0),
+            left: Val::Px(400.0),
+            ..default()
+        }),
+        GameOverText,
+    ));
+}
+
+fn despawn_game_over_text(
+    mut commands: Commands,
+    game_over_text_query: Query<Entity, With<GameOverText>>,
+) {
+    for entity in game_over_text_query.iter() {
+        commands.entity(entity).despawn();
     }
 }
 
 fn check_for_restart(
     keyboard_input: Res<ButtonInput<KeyCode>>,
-    game_state: Res<GameState>,
+    state: Res<State<AppState>>,
     mut restart_events: EventWriter<RestartEvent>,
 ) {
-    if game_state.is_game_over && keyboard_input.just_pressed(KeyCode::KeyR) {
+    if *state.get() == AppState::GameOver && keyboard_input.just_pressed(KeyCode::KeyR) {
         restart_events.send(RestartEvent);
     }
 }
 
+#[allow(clippy::type_complexity)]
 fn handle_restart_event(
     mut commands: Commands,
-    mut game_state: ResMut<GameState>,
     mut restart_events: EventReader<RestartEvent>,
-    player_query: Query<Entity, With<Player>>,
-    game_over_text_query: Query<Entity, With<GameOverText>>,
-    asset_server: Res<AssetServer>,
-    texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
+    level_query: Query<
+        Entity,
+        Or<(
+            With<Player>,
+            With<Ground>,
+            With<Pipe>,
+            With<Background>,
+            With<ScoreZone>,
+        )>,
+    >,
+    mut score: ResMut<Score>,
+    mut next_state: ResMut<NextState<AppState>>,
 ) {
     if !restart_events.is_empty() {
-        // Reset game state
-        game_state.is_game_over = false;
-
-        // Despawn existing entities
-        for entity in player_query.iter().chain(game_over_text_query.iter()) {
-            commands.entity(entity).despawn();
+        // Tear the level down so `OnEnter(AppState::Playing)` rebuilds it from scratch.
+        for entity in level_query.iter() {
+            commands.entity(entity).despawn_recursive();
         }
 
-        // Respawn player and ground
-        spawn_player(commands, asset_server, texture_atlas_layouts);
+        // A fresh run starts at zero, but the best score is preserved.
+        score.current = 0;
+
+        next_state.set(AppState::Playing);
         restart_events.clear();
     }
 }
 
-fn setup_background(commands: &mut Commands, asset_server: &Res<AssetServer>) {
-    let background_image = asset_server.load("textures/Background5.png");
+fn setup_background(commands: &mut Commands, assets: &AssetLoader) {
     commands.spawn((
         SpriteBundle {
-            texture: background_image,
+            texture: assets.images.background.clone(),
             transform: Transform {
                 // The scale might need adjusting depending on your image size and desired coverage
                 scale: Vec3::new(1.0, 1.0, 1.0),
@@ -374,21 +782,48 @@ fn setup_background(commands: &mut Commands, asset_server: &Res<AssetServer>) {
     ));
 }
 
-fn spawn_pipe(
-    commands: &mut Commands,
-    asset_server: &Res<AssetServer>,
-    texture_atlas_layouts: &mut ResMut<Assets<TextureAtlasLayout>>,
-) {
-    let texture_handle = asset_server.load("textures/PipeStyle5.png");
-    let layout = TextureAtlasLayout::from_grid(UVec2::new(32, 48), 4, 2, None, None);
-    let texture_atlas_layout = texture_atlas_layouts.add(layout);
+fn spawn_pipe(commands: &mut Commands, assets: &AssetLoader) {
+    let texture_handle = assets.images.pipe.clone();
+    let texture_atlas_layout = assets.layouts.pipe.clone();
+    let pipe_half_h = (48. * PIPE_SCALE.y) / 2.0;
     for i in 0..100 {
-        let y = if rand::random() { -200. } else { 250. };
+        let column_x = 400. + (400. * (i as f32));
+        // Randomize where the gap sits in each column, independently of the others.
+        let gap_center = -150. + rand::random::<f32>() * 300.;
+        let top_y = gap_center + GAP_HEIGHT / 2.0 + pipe_half_h;
+        let bottom_y = gap_center - GAP_HEIGHT / 2.0 - pipe_half_h;
+
+        // Top pipe — flipped so its mouth faces down into the gap.
+        commands.spawn((
+            SpriteBundle {
+                texture: texture_handle.clone(),
+                transform: Transform {
+                    translation: Vec3::new(column_x, top_y, 1.0),
+                    scale: PIPE_SCALE,
+                    ..Default::default()
+                },
+                sprite: Sprite {
+                    flip_y: true,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            TextureAtlas {
+                layout: texture_atlas_layout.clone(),
+                index: 0,
+            },
+            RigidBody::Fixed,
+            // Local half-extents; rapier scales them by the transform's PIPE_SCALE.
+            Collider::cuboid(16.0, 24.0),
+            Pipe,
+        ));
+
+        // Bottom pipe.
         commands.spawn((
             SpriteBundle {
                 texture: texture_handle.clone(),
                 transform: Transform {
-                    translation: Vec3::new(400. + (400. * (i as f32)), y, 1.0), // Position the player at the center
+                    translation: Vec3::new(column_x, bottom_y, 1.0),
                     scale: PIPE_SCALE,
                     ..Default::default()
                 },
@@ -398,7 +833,97 @@ fn spawn_pipe(
                 layout: texture_atlas_layout.clone(),
                 index: 0,
             },
+            RigidBody::Fixed,
+            Collider::cuboid(16.0, 24.0),
             Pipe,
         ));
+
+        // One score zone per column, scored when the bird clears `column_x`.
+        commands.spawn(ScoreZone {
+            x: column_x,
+            passed: false,
+        });
+    }
+}
+
+fn setup_score_ui(mut commands: Commands, assets: Res<AssetLoader>) {
+    commands.spawn((
+        TextBundle::from_section(
+            "Score: 0\nBest: 0",
+            TextStyle {
+                font: assets.fonts.fira.clone(),
+                font_size: 32.0,
+                color: Color::WHITE,
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            top: Val::Px(10.0),
+            left: Val::Px(10.0),
+            ..default()
+        }),
+        ScoreText,
+    ));
+}
+
+fn score_system(
+    mut commands: Commands,
+    player_query: Query<&Transform, With<Player>>,
+    mut zone_query: Query<&mut ScoreZone>,
+    mut score: ResMut<Score>,
+    sounds: Res<Sounds>,
+    audio_settings: Res<AudioSettings>,
+) {
+    let Ok(player_transform) = player_query.get_single() else {
+        return;
+    };
+    for mut zone in zone_query.iter_mut() {
+        if !zone.passed && player_transform.translation.x > zone.x {
+            zone.passed = true;
+            score.current += 1;
+            commands.spawn(AudioBundle {
+                source: sounds.score.clone(),
+                settings: one_shot(&audio_settings),
+            });
+        }
+    }
+}
+
+fn update_score_text(score: Res<Score>, mut query: Query<&mut Text, With<ScoreText>>) {
+    for mut text in &mut query {
+        text.sections[0].value = format!("Score: {}\nBest: {}", score.current, score.best);
+    }
+}
+
+fn persist_best_score(mut game_over_events: EventReader<GameOverEvent>, mut score: ResMut<Score>) {
+    if game_over_events.is_empty() {
+        return;
+    }
+    game_over_events.clear();
+    if score.current > score.best {
+        score.best = score.current;
+        save_best_score(score.best);
+    }
+}
+
+/// Path to the persisted high score under the user's config directory.
+fn best_score_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("flappy-bird").join("best_score.txt"))
+}
+
+fn load_best_score() -> u32 {
+    best_score_path()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|contents| contents.trim().parse().ok())
+        .unwrap_or(0)
+}
+
+fn save_best_score(best: u32) {
+    let Some(path) = best_score_path() else {
+        return;
+    };
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
     }
+    let _ = fs::write(path, best.to_string());
 }